@@ -4,7 +4,7 @@
 //! When a state transition spends bills, new bills are created in lesser or equal amount.
 
 use super::{StateMachine, User};
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 
 /// This state machine models a multi-user currency system. It tracks a set of bills in
 /// circulation, and updates that set when money is transferred.
@@ -20,21 +20,57 @@ pub struct Bill {
     serial: u64,
 }
 
+/// A running audit of the value that has entered and left the system over its
+/// whole lifetime. `circulating` is the value currently held in bills; the
+/// difference `minted - burned` must always equal it.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct ValueBalance {
+    /// Total value ever minted into existence.
+    pub minted: u64,
+    /// Total value ever destroyed by spending more than was received.
+    pub burned: u64,
+    /// Total value currently held in circulating bills.
+    pub circulating: u64,
+}
+
+/// Raised when the conservation-of-value invariant cannot be confirmed.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum BalanceError {
+    /// Summing the circulating bills overflowed `u64`; carries the partial sum
+    /// accumulated so far instead of panicking.
+    Overflow { partial_sum: u64 },
+    /// The accounted balance disagrees with the live bill set.
+    Inconsistent { accounted: u64, bills_total: u64 },
+}
+
 /// The State of a digital cash system. Primarily just the set of currently circulating bills.,
 /// but also a counter for the next serial number.
-#[derive(Clone, Debug, Eq, PartialEq)]
+#[derive(Clone, Debug)]
 pub struct State {
     /// The set of currently circulating bills
     bills: HashSet<Bill>,
     /// The next serial number to use when a bill is created.
     next_serial: u64,
+    /// A lifetime audit of minted, burned, and circulating value.
+    balance: ValueBalance,
 }
 
+/// Two states are equal when they hold the same bills and issue the same next
+/// serial. The value audit is derived bookkeeping and is intentionally excluded.
+impl PartialEq for State {
+    fn eq(&self, other: &Self) -> bool {
+        self.bills == other.bills && self.next_serial == other.next_serial
+    }
+}
+
+impl Eq for State {}
+
 impl State {
     pub fn new() -> Self {
         State {
             bills: HashSet::<Bill>::new(),
             next_serial: 0,
+            balance: ValueBalance::default(),
         }
     }
 
@@ -54,6 +90,38 @@ impl State {
         self.bills.insert(elem);
         self.increment_serial()
     }
+
+    /// Confirm the conservation-of-value invariant: the accounted balance
+    /// (`minted - burned`) must equal `circulating`, and both must equal the
+    /// live sum of all bill amounts. Bill summation uses checked arithmetic and
+    /// reports the partial sum on overflow rather than panicking.
+    pub fn check_invariant(&self) -> Result<(), BalanceError> {
+        let mut bills_total = 0u64;
+        for bill in &self.bills {
+            bills_total = bills_total
+                .checked_add(bill.amount)
+                .ok_or(BalanceError::Overflow {
+                    partial_sum: bills_total,
+                })?;
+        }
+
+        let accounted = self
+            .balance
+            .minted
+            .checked_sub(self.balance.burned)
+            .ok_or(BalanceError::Inconsistent {
+                accounted: 0,
+                bills_total,
+            })?;
+
+        if self.balance.circulating != accounted || self.balance.circulating != bills_total {
+            return Err(BalanceError::Inconsistent {
+                accounted,
+                bills_total,
+            });
+        }
+        Ok(())
+    }
 }
 
 impl FromIterator<Bill> for State {
@@ -61,6 +129,8 @@ impl FromIterator<Bill> for State {
         let mut state = State::new();
 
         for i in iter {
+            state.balance.minted = state.balance.minted.saturating_add(i.amount);
+            state.balance.circulating = state.balance.circulating.saturating_add(i.amount);
             state.add_bill(i)
         }
         state
@@ -73,6 +143,45 @@ impl<const N: usize> From<[Bill; N]> for State {
     }
 }
 
+/// A transfer that has not yet been authorized. Each spent bill carries a
+/// `User` "signature" asserting that the bill's declared owner approved the
+/// spend. Until those signatures are checked, the transfer cannot be applied.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct UnverifiedTransfer {
+    /// The bills being spent, each paired with the user who authorized the spend.
+    pub spends: Vec<(Bill, User)>,
+    pub receives: Vec<Bill>,
+}
+
+/// A transfer whose every spent bill has been authorized by its owner. Only
+/// verified transfers may be applied by [`DigitalCashSystem::next_state`].
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct VerifiedTransfer {
+    spends: Vec<Bill>,
+    receives: Vec<Bill>,
+}
+
+impl UnverifiedTransfer {
+    /// Check every spent bill's authorization against that bill's owner.
+    /// Returns a [`VerifiedTransfer`] only when all authorizations match;
+    /// otherwise returns [`CashError::SpendNotOwned`] on the first authorization
+    /// that doesn't match, so the caller can tell this apart from every other
+    /// way a transfer can be rejected. The error does not say which bill failed.
+    pub fn verify(self) -> Result<VerifiedTransfer, CashError> {
+        let mut spends = Vec::with_capacity(self.spends.len());
+        for (bill, authorizer) in self.spends {
+            if authorizer != bill.owner {
+                return Err(CashError::SpendNotOwned);
+            }
+            spends.push(bill);
+        }
+        Ok(VerifiedTransfer {
+            spends,
+            receives: self.receives,
+        })
+    }
+}
+
 /// The state transitions that users can make in a digital cash system
 pub enum CashTransaction {
     /// Mint a single new bill owned by the minter
@@ -82,71 +191,76 @@ pub enum CashTransaction {
     /// The total amount received must be less than or equal to the amount spent.
     /// The discrepancy between the amount sent and received is destroyed. Therefore,
     /// no dedicated burn transaction is required.
-    Transfer {
-        spends: Vec<Bill>,
-        receives: Vec<Bill>,
-    },
+    ///
+    /// Only the verified form is accepted, so that authorization is checked
+    /// before a transfer ever reaches the state machine.
+    Transfer(VerifiedTransfer),
+    /// Apply an ordered list of sub-transactions as a single atomic unit. Each
+    /// sub-transaction sees the intermediate state produced by the previous one,
+    /// so a bill minted or received earlier in the batch can be spent later. If
+    /// any sub-transaction is rejected, the whole batch is rejected and the
+    /// original state is left unchanged.
+    Batch(Vec<CashTransaction>),
 }
 
 
-impl DigitalCashSystem {
-    fn is_empty_receive_fails(receives: &[Bill]) -> bool {
-        receives.is_empty()
-    }
-
-    fn is_overflow_receives_fails(total_spent: u64, total_received: u64) -> bool {
-        total_received > total_spent
-    }
-
-    fn has_incorrect_serial(
-        receives: &[Bill],
-        spends_serials: &HashSet<u64>,
-        existing_bills: &HashSet<u64>
-    ) -> bool {
-        let mut new_serials = HashSet::new();
-        for bill in receives {
-            if bill.amount == 0 {
-                println!("Found bill with zero amount: {:?}", bill);
-                return true; // Receiving bill with zero amount is invalid
-            }
-
-            // Ensure the serial number is unique and correct
-            if spends_serials.contains(&bill.serial) || new_serials.contains(&bill.serial) || existing_bills.contains(&bill.serial) {
-                println!("Found duplicate or incorrect serial: {:?}", bill.serial);
-                return true; // Duplicate serial numbers are not allowed
-            }
+/// The ways in which a cash transaction can be rejected. Unlike the old
+/// behaviour — which silently returned the unchanged state and printed to
+/// stdout — every rejection now names the rule that was broken, and the
+/// numeric variants also carry the offending sum or serial.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum CashError {
+    /// A minted or received bill had a value of zero.
+    ZeroAmount,
+    /// A transfer received no bills at all.
+    EmptyReceives,
+    /// The bills received were worth more than the bills spent.
+    ValueOverflow {
+        total_spent: u64,
+        total_received: u64,
+    },
+    /// A spent bill does not exist in the current state.
+    UnknownBill { serial: u64 },
+    /// A serial number was spent twice or a received serial was not the next
+    /// serial the state would issue.
+    DuplicateSerial { serial: u64 },
+    /// A spent bill was not authorized by its owner.
+    SpendNotOwned,
+}
 
-            new_serials.insert(bill.serial);
+impl CashError {
+    /// Return the offending amount or serial for the numeric variants, so that
+    /// downstream tooling can point at the exact value that broke the rule.
+    pub fn invalid_value(&self) -> Option<u64> {
+        match self {
+            CashError::ValueOverflow { total_received, .. } => Some(*total_received),
+            CashError::UnknownBill { serial } => Some(*serial),
+            CashError::DuplicateSerial { serial } => Some(*serial),
+            CashError::ZeroAmount | CashError::EmptyReceives | CashError::SpendNotOwned => None,
         }
-        false
     }
+}
 
+impl DigitalCashSystem {
     fn get_total_amount(bills: &[Bill]) -> u64 {
-        bills.iter().map(|bill| bill.amount).sum()
+        bills
+            .iter()
+            .map(|bill| bill.amount)
+            .fold(0u64, u64::saturating_add)
     }
 
-    fn get_serials_set_from_vec(bills: &[Bill]) -> HashSet<u64> {
-        bills.iter().map(|bill| bill.serial).collect()
-    }
-
-    fn get_serials_set_from_hashset(bills: &HashSet<Bill>) -> HashSet<u64> {
-        bills.iter().map(|bill| bill.serial).collect()
-    }
-}
-
-/// We model this system as a state machine with two possible transitions
-impl StateMachine for DigitalCashSystem {
-    type State = State;
-    type Transition = CashTransaction;
-
-    fn next_state(starting_state: &Self::State, t: &Self::Transition) -> Self::State {
+    /// Apply a transition, returning the resulting state or the specific reason
+    /// the transition was rejected.
+    pub fn next_state(
+        starting_state: &State,
+        t: &CashTransaction,
+    ) -> Result<State, CashError> {
         let mut next_state = starting_state.clone();
 
         match t {
             CashTransaction::Mint { minter, amount } => {
                 if *amount == 0 {
-                    println!("Minting bill with zero amount is invalid.");
-                    return starting_state.clone(); // Minting bill with zero amount is invalid
+                    return Err(CashError::ZeroAmount);
                 }
                 let new_bill = Bill {
                     owner: minter.clone(),
@@ -154,36 +268,45 @@ impl StateMachine for DigitalCashSystem {
                     serial: next_state.next_serial(),
                 };
                 next_state.add_bill(new_bill);
+                next_state.balance.minted = next_state.balance.minted.saturating_add(*amount);
+                next_state.balance.circulating =
+                    next_state.balance.circulating.saturating_add(*amount);
             }
-            CashTransaction::Transfer { spends, receives } => {
-                // Check for empty receives
-                if DigitalCashSystem::is_empty_receive_fails(receives) {
-                    println!("Transfer failed: empty receives.");
-                    return starting_state.clone(); // Empty receives should fail
+            CashTransaction::Transfer(VerifiedTransfer { spends, receives }) => {
+                if receives.is_empty() {
+                    return Err(CashError::EmptyReceives);
                 }
 
                 let total_spent = DigitalCashSystem::get_total_amount(spends);
                 let total_received = DigitalCashSystem::get_total_amount(receives);
-
-                // Check for overflow in receives
-                if DigitalCashSystem::is_overflow_receives_fails(total_spent, total_received) {
-                    println!("Transfer failed: overflow in receives. Total spent: {}, Total received: {}", total_spent, total_received);
-                    return starting_state.clone(); // Total received should not exceed total spent
+                if total_received > total_spent {
+                    return Err(CashError::ValueOverflow {
+                        total_spent,
+                        total_received,
+                    });
                 }
 
-                // Check if all spent bills exist in the current state
-                if !spends.iter().all(|bill| starting_state.bills.contains(bill)) {
-                    println!("Transfer failed: not all spent bills exist in the current state.");
-                    return starting_state.clone(); // All spent bills must exist in the current state
+                // Every spent bill must exist, and no serial may be spent twice.
+                let mut spent_serials = HashSet::new();
+                for bill in spends {
+                    if !starting_state.bills.contains(bill) {
+                        return Err(CashError::UnknownBill { serial: bill.serial });
+                    }
+                    if !spent_serials.insert(bill.serial) {
+                        return Err(CashError::DuplicateSerial { serial: bill.serial });
+                    }
                 }
 
-                let spends_serials = DigitalCashSystem::get_serials_set_from_vec(spends);
-                let existing_serials = DigitalCashSystem::get_serials_set_from_hashset(&starting_state.bills);
-
-                // Ensure no duplicates in received bills and correct serials
-                if DigitalCashSystem::has_incorrect_serial(receives, &spends_serials, &existing_serials) {
-                    println!("Transfer failed: incorrect serials or duplicates found in received bills.");
-                    return starting_state.clone(); // Incorrect serials or duplicates should fail
+                // Received bills must be non-zero and carry the serials the
+                // state is about to issue, in order.
+                for (offset, bill) in receives.iter().enumerate() {
+                    if bill.amount == 0 {
+                        return Err(CashError::ZeroAmount);
+                    }
+                    let expected = starting_state.next_serial() + offset as u64;
+                    if bill.serial != expected {
+                        return Err(CashError::DuplicateSerial { serial: bill.serial });
+                    }
                 }
 
                 // Transition to next state by removing spent bills and adding received bills
@@ -193,10 +316,269 @@ impl StateMachine for DigitalCashSystem {
                 for bill in receives {
                     next_state.add_bill(bill.clone());
                 }
+
+                // The discrepancy between value spent and received is destroyed.
+                let burned = total_spent - total_received;
+                next_state.balance.burned = next_state.balance.burned.saturating_add(burned);
+                next_state.balance.circulating =
+                    next_state.balance.circulating.saturating_sub(burned);
+            }
+            CashTransaction::Batch(sub_transactions) => {
+                // Thread a trial state through every sub-transaction before
+                // committing. The `?` propagates the first rejection, leaving
+                // the caller's starting state untouched.
+                let mut trial = starting_state.clone();
+                for sub in sub_transactions {
+                    trial = DigitalCashSystem::next_state(&trial, sub)?;
+                }
+                next_state = trial;
             }
         }
 
-        next_state
+        Ok(next_state)
+    }
+}
+
+/// We model this system as a state machine with three possible transitions. The
+/// `StateMachine` contract is infallible, so rejected transitions surface here
+/// as the unchanged state; callers that need the rejection reason should use
+/// the inherent [`DigitalCashSystem::next_state`], which returns a `Result`.
+impl StateMachine for DigitalCashSystem {
+    type State = State;
+    type Transition = CashTransaction;
+
+    fn next_state(starting_state: &Self::State, t: &Self::Transition) -> Self::State {
+        DigitalCashSystem::next_state(starting_state, t)
+            .unwrap_or_else(|_| starting_state.clone())
+    }
+}
+
+/// A pending transfer waiting to be confirmed into `State`. It carries the same
+/// spends/receives as a [`CashTransaction::Transfer`], but lives in the mempool
+/// rather than the confirmed bill set.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Transfer {
+    pub spends: Vec<Bill>,
+    pub receives: Vec<Bill>,
+}
+
+/// Identity of a pooled transfer. A transfer is named by the lowest serial it
+/// produces, which is unique because serial numbers are never reused.
+pub type TxId = u64;
+
+/// What the pool knows about a single bill serial: how far it is from being
+/// confirmed, and whether a pooled transfer is already spending it.
+#[derive(Clone, Debug, Eq, PartialEq)]
+struct CoinState {
+    /// `0` when the bill already lives in the confirmed `State`; otherwise the
+    /// depth of the pending transfer that produced it, which is one more than
+    /// the deepest bill that transfer spends.
+    depth: u32,
+    /// The pooled transfer currently spending this bill, if any. Used to reject
+    /// double spends within the pool.
+    is_spend_by: Option<TxId>,
+}
+
+/// An unconfirmed-transfer mempool layered over [`State`].
+///
+/// Unlike the bare state machine, the pool can hold transfers that spend bills
+/// which are not yet in `State` because they are the outputs of other still
+/// pending transfers. It tracks the resulting dependency graph so that ready
+/// transfers — those whose every input is already confirmed — can be replayed
+/// through [`DigitalCashSystem::next_state`] in dependency order.
+pub struct CashPool {
+    /// Dependency information for every serial the pool is aware of.
+    coins: HashMap<u64, CoinState>,
+    /// The pending transfers, keyed by their id, alongside their depth.
+    transfers: HashMap<TxId, (Transfer, u32)>,
+    /// The deepest dependency chain the pool will accept.
+    max_depth: u32,
+}
+
+impl CashPool {
+    /// Create an empty pool seeded from a confirmed `State`. Every bill already
+    /// in circulation is recorded at `depth == 0`.
+    pub fn new(state: &State, max_depth: u32) -> Self {
+        let coins = state
+            .bills
+            .iter()
+            .map(|bill| {
+                (
+                    bill.serial,
+                    CoinState {
+                        depth: 0,
+                        is_spend_by: None,
+                    },
+                )
+            })
+            .collect();
+        CashPool {
+            coins,
+            transfers: HashMap::new(),
+            max_depth,
+        }
+    }
+
+    /// Attempt to add a transfer to the pool.
+    ///
+    /// Returns the new transfer's id, or `None` if it would exceed `max_depth`,
+    /// double spend a serial already claimed by another pooled transfer, or
+    /// spend a serial the pool has never seen.
+    pub fn insert(&mut self, transfer: Transfer) -> Option<TxId> {
+        if transfer.receives.is_empty() {
+            return None;
+        }
+
+        // Walk the inputs to find the transfer's depth and to detect double
+        // spends, without mutating anything until we know the transfer is valid.
+        let mut depth = 0;
+        for spend in &transfer.spends {
+            let coin = self.coins.get(&spend.serial)?;
+            if coin.is_spend_by.is_some() {
+                return None; // serial already being spent by another pooled tx
+            }
+            depth = depth.max(coin.depth + 1);
+        }
+        if depth > self.max_depth {
+            return None;
+        }
+
+        let id = transfer.receives.iter().map(|bill| bill.serial).min()?;
+
+        // Commit: mark inputs as spent and register the produced bills.
+        for spend in &transfer.spends {
+            if let Some(coin) = self.coins.get_mut(&spend.serial) {
+                coin.is_spend_by = Some(id);
+            }
+        }
+        for bill in &transfer.receives {
+            self.coins.insert(
+                bill.serial,
+                CoinState {
+                    depth,
+                    is_spend_by: None,
+                },
+            );
+        }
+        self.transfers.insert(id, (transfer, depth));
+        Some(id)
+    }
+
+    /// Remove a transfer from the pool, releasing the bills it was spending and
+    /// forgetting the bills it produced.
+    pub fn remove(&mut self, id: TxId) -> Option<Transfer> {
+        let (transfer, _) = self.transfers.remove(&id)?;
+        for spend in &transfer.spends {
+            if let Some(coin) = self.coins.get_mut(&spend.serial) {
+                if coin.is_spend_by == Some(id) {
+                    coin.is_spend_by = None;
+                }
+            }
+        }
+        for bill in &transfer.receives {
+            self.coins.remove(&bill.serial);
+        }
+        Some(transfer)
+    }
+
+    /// Iterate over the transfers whose every spent bill is already confirmed,
+    /// yielded shallowest-first so they can be fed to `next_state` in dependency
+    /// order.
+    pub fn ready(&self) -> impl Iterator<Item = &Transfer> {
+        let mut ready: Vec<&(Transfer, u32)> = self
+            .transfers
+            .values()
+            .filter(|(transfer, _)| {
+                transfer.spends.iter().all(|spend| {
+                    self.coins
+                        .get(&spend.serial)
+                        .map(|coin| coin.depth == 0)
+                        .unwrap_or(false)
+                })
+            })
+            .collect();
+        ready.sort_by_key(|(_, depth)| *depth);
+        ready.into_iter().map(|(transfer, _)| transfer)
+    }
+}
+
+/// Raised when a [`Wallet`] that only tracks a restricted set of users is asked
+/// about a user it does not own.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum WalletError {
+    /// The queried user is not tracked by this wallet.
+    ForeignAddress(User),
+}
+
+/// A read-only, owner-scoped view over a cash [`State`].
+///
+/// A `Wallet` answers balance and coin-selection queries without exposing the
+/// raw bill set. It can optionally be restricted to a set of owned users, in
+/// which case queries about any other user are rejected with
+/// [`WalletError::ForeignAddress`].
+pub struct Wallet {
+    /// A snapshot of the circulating bills.
+    bills: HashSet<Bill>,
+    /// The users this wallet is allowed to answer for, or `None` to track all.
+    owned: Option<HashSet<User>>,
+}
+
+impl Wallet {
+    /// Build a wallet that can answer for every user in the state.
+    pub fn new(state: &State) -> Self {
+        Wallet {
+            bills: state.bills.clone(),
+            owned: None,
+        }
+    }
+
+    /// Build a wallet restricted to a specific set of users. Queries about any
+    /// user outside this set return [`WalletError::ForeignAddress`].
+    pub fn restricted_to(state: &State, users: impl IntoIterator<Item = User>) -> Self {
+        Wallet {
+            bills: state.bills.clone(),
+            owned: Some(users.into_iter().collect()),
+        }
+    }
+
+    fn ensure_tracked(&self, user: &User) -> Result<(), WalletError> {
+        match &self.owned {
+            Some(owned) if !owned.contains(user) => {
+                Err(WalletError::ForeignAddress(user.clone()))
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Sum the value of every bill owned by `user`.
+    pub fn total_assets_of(&self, user: &User) -> Result<u64, WalletError> {
+        self.ensure_tracked(user)?;
+        Ok(self
+            .bills
+            .iter()
+            .filter(|bill| &bill.owner == user)
+            .map(|bill| bill.amount)
+            .fold(0u64, u64::saturating_add))
+    }
+
+    /// List every bill owned by `user`.
+    pub fn all_coins_of(&self, user: &User) -> Result<Vec<Bill>, WalletError> {
+        self.ensure_tracked(user)?;
+        Ok(self
+            .bills
+            .iter()
+            .filter(|bill| &bill.owner == user)
+            .cloned()
+            .collect())
+    }
+
+    /// Sum the value of every circulating bill, regardless of owner. Useful for
+    /// checking the conservation/burn invariant.
+    pub fn net_worth(&self) -> u64 {
+        self.bills
+            .iter()
+            .map(|bill| bill.amount)
+            .fold(0u64, u64::saturating_add)
     }
 }
 
@@ -217,7 +599,7 @@ fn sm_5_mint_new_cash() {
         amount: 20,
         serial: 0,
     }]);
-    assert_eq!(end, expected);
+    assert_eq!(end, Ok(expected));
 }
 
 #[test]
@@ -229,7 +611,7 @@ fn sm_5_overflow_receives_fails() {
     }]);
     let end = DigitalCashSystem::next_state(
         &start,
-        &CashTransaction::Transfer {
+        &CashTransaction::Transfer(VerifiedTransfer {
             spends: vec![Bill {
                 owner: User::Alice,
                 amount: 42,
@@ -247,14 +629,9 @@ fn sm_5_overflow_receives_fails() {
                     serial: 2,
                 },
             ],
-        },
+        }),
     );
-    let expected = State::from([Bill {
-        owner: User::Alice,
-        amount: 42,
-        serial: 0,
-    }]);
-    assert_eq!(end, expected);
+    assert!(matches!(end, Err(CashError::ValueOverflow { .. })));
 }
 
 #[test]
@@ -266,21 +643,16 @@ fn sm_5_empty_spend_fails() {
     }]);
     let end = DigitalCashSystem::next_state(
         &start,
-        &CashTransaction::Transfer {
+        &CashTransaction::Transfer(VerifiedTransfer {
             spends: vec![],
             receives: vec![Bill {
                 owner: User::Alice,
                 amount: 15,
                 serial: 1,
             }],
-        },
+        }),
     );
-    let expected = State::from([Bill {
-        owner: User::Alice,
-        amount: 20,
-        serial: 0,
-    }]);
-    assert_eq!(end, expected);
+    assert!(matches!(end, Err(CashError::ValueOverflow { .. })));
 }
 
 #[test]
@@ -292,18 +664,16 @@ fn sm_5_empty_receive_fails() {
     }]);
     let end = DigitalCashSystem::next_state(
         &start,
-        &CashTransaction::Transfer {
+        &CashTransaction::Transfer(VerifiedTransfer {
             spends: vec![Bill {
                 owner: User::Alice,
                 amount: 20,
                 serial: 0,
             }],
             receives: vec![],
-        },
+        }),
     );
-    let mut expected = State::from([]);
-    expected.set_serial(1);
-    assert_eq!(end, expected);
+    assert_eq!(end, Err(CashError::EmptyReceives));
 }
 
 #[test]
@@ -315,7 +685,7 @@ fn sm_5_output_value_0_fails() {
     }]);
     let end = DigitalCashSystem::next_state(
         &start,
-        &CashTransaction::Transfer {
+        &CashTransaction::Transfer(VerifiedTransfer {
             spends: vec![Bill {
                 owner: User::Alice,
                 amount: 20,
@@ -326,14 +696,9 @@ fn sm_5_output_value_0_fails() {
                 amount: 0,
                 serial: 1,
             }],
-        },
+        }),
     );
-    let expected = State::from([Bill {
-        owner: User::Alice,
-        amount: 20,
-        serial: 0,
-    }]);
-    assert_eq!(end, expected);
+    assert_eq!(end, Err(CashError::ZeroAmount));
 }
 
 #[test]
@@ -345,7 +710,7 @@ fn sm_5_serial_number_already_seen_fails() {
     }]);
     let end = DigitalCashSystem::next_state(
         &start,
-        &CashTransaction::Transfer {
+        &CashTransaction::Transfer(VerifiedTransfer {
             spends: vec![Bill {
                 owner: User::Alice,
                 amount: 20,
@@ -356,14 +721,9 @@ fn sm_5_serial_number_already_seen_fails() {
                 amount: 18,
                 serial: 0,
             }],
-        },
+        }),
     );
-    let expected = State::from([Bill {
-        owner: User::Alice,
-        amount: 20,
-        serial: 0,
-    }]);
-    assert_eq!(end, expected);
+    assert_eq!(end, Err(CashError::DuplicateSerial { serial: 0 }));
 }
 
 #[test]
@@ -375,7 +735,7 @@ fn sm_5_spending_and_receiving_same_bill_fails() {
     }]);
     let end = DigitalCashSystem::next_state(
         &start,
-        &CashTransaction::Transfer {
+        &CashTransaction::Transfer(VerifiedTransfer {
             spends: vec![Bill {
                 owner: User::Alice,
                 amount: 20,
@@ -386,14 +746,9 @@ fn sm_5_spending_and_receiving_same_bill_fails() {
                 amount: 20,
                 serial: 0,
             }],
-        },
+        }),
     );
-    let expected = State::from([Bill {
-        owner: User::Alice,
-        amount: 20,
-        serial: 0,
-    }]);
-    assert_eq!(end, expected);
+    assert_eq!(end, Err(CashError::DuplicateSerial { serial: 0 }));
 }
 
 #[test]
@@ -405,7 +760,7 @@ fn sm_5_receiving_bill_with_incorrect_serial_fails() {
     }]);
     let end = DigitalCashSystem::next_state(
         &start,
-        &CashTransaction::Transfer {
+        &CashTransaction::Transfer(VerifiedTransfer {
             spends: vec![Bill {
                 owner: User::Alice,
                 amount: 20,
@@ -423,14 +778,9 @@ fn sm_5_receiving_bill_with_incorrect_serial_fails() {
                     serial: 4000,
                 },
             ],
-        },
+        }),
     );
-    let expected = State::from([Bill {
-        owner: User::Alice,
-        amount: 20,
-        serial: 0,
-    }]);
-    assert_eq!(end, expected);
+    assert_eq!(end, Err(CashError::DuplicateSerial { serial: u64::MAX }));
 }
 
 #[test]
@@ -442,7 +792,7 @@ fn sm_5_spending_bill_with_incorrect_amount_fails() {
     }]);
     let end = DigitalCashSystem::next_state(
         &start,
-        &CashTransaction::Transfer {
+        &CashTransaction::Transfer(VerifiedTransfer {
             spends: vec![Bill {
                 owner: User::Alice,
                 amount: 40,
@@ -453,14 +803,9 @@ fn sm_5_spending_bill_with_incorrect_amount_fails() {
                 amount: 40,
                 serial: 1,
             }],
-        },
+        }),
     );
-    let expected = State::from([Bill {
-        owner: User::Alice,
-        amount: 20,
-        serial: 0,
-    }]);
-    assert_eq!(end, expected);
+    assert_eq!(end, Err(CashError::UnknownBill { serial: 0 }));
 }
 
 #[test]
@@ -472,7 +817,7 @@ fn sm_5_spending_same_bill_fails() {
     }]);
     let end = DigitalCashSystem::next_state(
         &start,
-        &CashTransaction::Transfer {
+        &CashTransaction::Transfer(VerifiedTransfer {
             spends: vec![
                 Bill {
                     owner: User::Alice,
@@ -502,14 +847,9 @@ fn sm_5_spending_same_bill_fails() {
                     serial: 3,
                 },
             ],
-        },
+        }),
     );
-    let expected = State::from([Bill {
-        owner: User::Alice,
-        amount: 40,
-        serial: 0,
-    }]);
-    assert_eq!(end, expected);
+    assert_eq!(end, Err(CashError::DuplicateSerial { serial: 0 }));
 }
 
 #[test]
@@ -528,7 +868,7 @@ fn sm_5_spending_more_than_bill_fails() {
     ]);
     let end = DigitalCashSystem::next_state(
         &start,
-        &CashTransaction::Transfer {
+        &CashTransaction::Transfer(VerifiedTransfer {
             spends: vec![
                 Bill {
                     owner: User::Alice,
@@ -558,21 +898,9 @@ fn sm_5_spending_more_than_bill_fails() {
                     serial: 4,
                 },
             ],
-        },
+        }),
     );
-    let expected = State::from([
-        Bill {
-            owner: User::Alice,
-            amount: 40,
-            serial: 0,
-        },
-        Bill {
-            owner: User::Charlie,
-            amount: 42,
-            serial: 1,
-        },
-    ]);
-    assert_eq!(end, expected);
+    assert!(matches!(end, Err(CashError::ValueOverflow { .. })));
 }
 
 #[test]
@@ -584,7 +912,7 @@ fn sm_5_spending_non_existent_bill_fails() {
     }]);
     let end = DigitalCashSystem::next_state(
         &start,
-        &CashTransaction::Transfer {
+        &CashTransaction::Transfer(VerifiedTransfer {
             spends: vec![Bill {
                 owner: User::Bob,
                 amount: 1000,
@@ -595,14 +923,9 @@ fn sm_5_spending_non_existent_bill_fails() {
                 amount: 1000,
                 serial: 33,
             }],
-        },
+        }),
     );
-    let expected = State::from([Bill {
-        owner: User::Alice,
-        amount: 32,
-        serial: 0,
-    }]);
-    assert_eq!(end, expected);
+    assert_eq!(end, Err(CashError::UnknownBill { serial: 32 }));
 }
 
 #[test]
@@ -614,7 +937,7 @@ fn sm_5_spending_from_alice_to_all() {
     }]);
     let end = DigitalCashSystem::next_state(
         &start,
-        &CashTransaction::Transfer {
+        &CashTransaction::Transfer(VerifiedTransfer {
             spends: vec![Bill {
                 owner: User::Alice,
                 amount: 42,
@@ -637,7 +960,7 @@ fn sm_5_spending_from_alice_to_all() {
                     serial: 3,
                 },
             ],
-        },
+        }),
     );
     let mut expected = State::from([
         Bill {
@@ -657,7 +980,7 @@ fn sm_5_spending_from_alice_to_all() {
         },
     ]);
     expected.set_serial(4);
-    assert_eq!(end, expected);
+    assert_eq!(end, Ok(expected));
 }
 
 #[test]
@@ -669,7 +992,7 @@ fn sm_5_spending_from_bob_to_all() {
     }]);
     let end = DigitalCashSystem::next_state(
         &start,
-        &CashTransaction::Transfer {
+        &CashTransaction::Transfer(VerifiedTransfer {
             spends: vec![Bill {
                 owner: User::Bob,
                 amount: 42,
@@ -692,7 +1015,7 @@ fn sm_5_spending_from_bob_to_all() {
                     serial: 3,
                 },
             ],
-        },
+        }),
     );
     let mut expected = State::from([
         Bill {
@@ -712,7 +1035,7 @@ fn sm_5_spending_from_bob_to_all() {
         },
     ]);
     expected.set_serial(4);
-    assert_eq!(end, expected);
+    assert_eq!(end, Ok(expected));
 }
 
 #[test]
@@ -732,7 +1055,7 @@ fn sm_5_spending_from_charlie_to_all() {
     start.set_serial(59);
     let end = DigitalCashSystem::next_state(
         &start,
-        &CashTransaction::Transfer {
+        &CashTransaction::Transfer(VerifiedTransfer {
             spends: vec![Bill {
                 owner: User::Charlie,
                 amount: 68,
@@ -755,7 +1078,7 @@ fn sm_5_spending_from_charlie_to_all() {
                     serial: 61,
                 },
             ],
-        },
+        }),
     );
     let mut expected = State::from([
         Bill {
@@ -780,5 +1103,284 @@ fn sm_5_spending_from_charlie_to_all() {
         },
     ]);
     expected.set_serial(62);
-    assert_eq!(end, expected);
+    assert_eq!(end, Ok(expected));
+}
+
+#[test]
+fn sm_5_pool_chains_pending_transfers() {
+    let start = State::from([Bill {
+        owner: User::Alice,
+        amount: 20,
+        serial: 0,
+    }]);
+    let mut pool = CashPool::new(&start, 4);
+
+    // Alice splits her confirmed bill; this transfer is ready immediately.
+    let split = Transfer {
+        spends: vec![Bill {
+            owner: User::Alice,
+            amount: 20,
+            serial: 0,
+        }],
+        receives: vec![
+            Bill {
+                owner: User::Alice,
+                amount: 15,
+                serial: 1,
+            },
+            Bill {
+                owner: User::Bob,
+                amount: 5,
+                serial: 2,
+            },
+        ],
+    };
+    let split_id = pool.insert(split).expect("split is insertable");
+
+    // Bob forwards a bill that only exists as the output of the pending split.
+    let forward = Transfer {
+        spends: vec![Bill {
+            owner: User::Bob,
+            amount: 5,
+            serial: 2,
+        }],
+        receives: vec![Bill {
+            owner: User::Charlie,
+            amount: 5,
+            serial: 3,
+        }],
+    };
+    pool.insert(forward).expect("dependent transfer is insertable");
+
+    // Only the split spends confirmed bills, so it is the sole ready transfer.
+    let ready: Vec<&Transfer> = pool.ready().collect();
+    assert_eq!(ready.len(), 1);
+    assert_eq!(ready[0].receives[0].serial, 1);
+
+    // Removing the split releases its inputs and forgets its outputs.
+    pool.remove(split_id);
+    assert_eq!(pool.ready().count(), 0);
+}
+
+#[test]
+fn sm_5_pool_rejects_double_spend() {
+    let start = State::from([Bill {
+        owner: User::Alice,
+        amount: 20,
+        serial: 0,
+    }]);
+    let mut pool = CashPool::new(&start, 4);
+
+    let spend = Bill {
+        owner: User::Alice,
+        amount: 20,
+        serial: 0,
+    };
+    pool.insert(Transfer {
+        spends: vec![spend.clone()],
+        receives: vec![Bill {
+            owner: User::Bob,
+            amount: 20,
+            serial: 1,
+        }],
+    })
+    .expect("first spend is insertable");
+
+    // A second transfer spending the same serial must be rejected.
+    let second = pool.insert(Transfer {
+        spends: vec![spend],
+        receives: vec![Bill {
+            owner: User::Charlie,
+            amount: 20,
+            serial: 2,
+        }],
+    });
+    assert_eq!(second, None);
+}
+
+#[test]
+fn sm_5_verify_rejects_unauthorized_spend() {
+    let alices_bill = Bill {
+        owner: User::Alice,
+        amount: 20,
+        serial: 0,
+    };
+
+    // Bob tries to spend Alice's bill by signing it himself.
+    let forged = UnverifiedTransfer {
+        spends: vec![(alices_bill.clone(), User::Bob)],
+        receives: vec![Bill {
+            owner: User::Bob,
+            amount: 20,
+            serial: 1,
+        }],
+    };
+    assert_eq!(forged.verify(), Err(CashError::SpendNotOwned));
+
+    // Alice authorizing her own bill verifies successfully.
+    let honest = UnverifiedTransfer {
+        spends: vec![(alices_bill, User::Alice)],
+        receives: vec![Bill {
+            owner: User::Bob,
+            amount: 20,
+            serial: 1,
+        }],
+    };
+    assert!(honest.verify().is_ok());
+}
+
+#[test]
+fn sm_5_batch_applies_atomically() {
+    let start = State::new();
+    // Mint a bill, then immediately split it, all in one indivisible batch.
+    let end = DigitalCashSystem::next_state(
+        &start,
+        &CashTransaction::Batch(vec![
+            CashTransaction::Mint {
+                minter: User::Alice,
+                amount: 20,
+            },
+            CashTransaction::Transfer(VerifiedTransfer {
+                spends: vec![Bill {
+                    owner: User::Alice,
+                    amount: 20,
+                    serial: 0,
+                }],
+                receives: vec![
+                    Bill {
+                        owner: User::Alice,
+                        amount: 15,
+                        serial: 1,
+                    },
+                    Bill {
+                        owner: User::Bob,
+                        amount: 5,
+                        serial: 2,
+                    },
+                ],
+            }),
+        ]),
+    );
+
+    let mut expected = State::from([
+        Bill {
+            owner: User::Alice,
+            amount: 15,
+            serial: 1,
+        },
+        Bill {
+            owner: User::Bob,
+            amount: 5,
+            serial: 2,
+        },
+    ]);
+    expected.set_serial(3);
+    assert_eq!(end, Ok(expected));
+}
+
+#[test]
+fn sm_5_batch_rejected_leaves_state_unchanged() {
+    let start = State::new();
+    // The second sub-transaction spends a bill that was never minted, so the
+    // whole batch — including the valid mint — must be rejected.
+    let end = DigitalCashSystem::next_state(
+        &start,
+        &CashTransaction::Batch(vec![
+            CashTransaction::Mint {
+                minter: User::Alice,
+                amount: 20,
+            },
+            CashTransaction::Transfer(VerifiedTransfer {
+                spends: vec![Bill {
+                    owner: User::Bob,
+                    amount: 99,
+                    serial: 7,
+                }],
+                receives: vec![Bill {
+                    owner: User::Bob,
+                    amount: 99,
+                    serial: 1,
+                }],
+            }),
+        ]),
+    );
+    assert_eq!(end, Err(CashError::UnknownBill { serial: 7 }));
+}
+
+#[test]
+fn sm_5_wallet_reports_balances_and_net_worth() {
+    let state = State::from([
+        Bill {
+            owner: User::Alice,
+            amount: 10,
+            serial: 0,
+        },
+        Bill {
+            owner: User::Alice,
+            amount: 5,
+            serial: 1,
+        },
+        Bill {
+            owner: User::Bob,
+            amount: 7,
+            serial: 2,
+        },
+    ]);
+    let wallet = Wallet::new(&state);
+
+    assert_eq!(wallet.total_assets_of(&User::Alice), Ok(15));
+    assert_eq!(wallet.all_coins_of(&User::Alice).map(|c| c.len()), Ok(2));
+    assert_eq!(wallet.net_worth(), 22);
+}
+
+#[test]
+fn sm_5_restricted_wallet_rejects_foreign_user() {
+    let state = State::from([Bill {
+        owner: User::Alice,
+        amount: 10,
+        serial: 0,
+    }]);
+    let wallet = Wallet::restricted_to(&state, [User::Alice]);
+
+    assert_eq!(wallet.total_assets_of(&User::Alice), Ok(10));
+    assert_eq!(
+        wallet.total_assets_of(&User::Bob),
+        Err(WalletError::ForeignAddress(User::Bob))
+    );
+}
+
+#[test]
+fn sm_5_value_balance_tracks_mint_and_burn() {
+    // Mint 20, then spend it forwarding only 15 — burning 5.
+    let minted = DigitalCashSystem::next_state(
+        &State::new(),
+        &CashTransaction::Mint {
+            minter: User::Alice,
+            amount: 20,
+        },
+    )
+    .unwrap();
+    assert_eq!(minted.check_invariant(), Ok(()));
+
+    let burned = DigitalCashSystem::next_state(
+        &minted,
+        &CashTransaction::Transfer(VerifiedTransfer {
+            spends: vec![Bill {
+                owner: User::Alice,
+                amount: 20,
+                serial: 0,
+            }],
+            receives: vec![Bill {
+                owner: User::Bob,
+                amount: 15,
+                serial: 1,
+            }],
+        }),
+    )
+    .unwrap();
+
+    assert_eq!(burned.balance.minted, 20);
+    assert_eq!(burned.balance.burned, 5);
+    assert_eq!(burned.balance.circulating, 15);
+    assert_eq!(burned.check_invariant(), Ok(()));
 }
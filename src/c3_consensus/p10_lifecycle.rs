@@ -0,0 +1,221 @@
+//! A consensus engine does more than seal a finished header. Real engines
+//! initialize consensus-controlled fields before mining and mutate state after
+//! a block is sealed. Modeled on go-ethereum's `Prepare`/`Finalize` and
+//! Qitmeer's `Prepare`/`Finalize`/`Generate`, we add a `prepare` hook that
+//! populates engine-controlled header fields before `seal`, and a `finalize`
+//! hook that applies post-block effects such as crediting a mining or validator
+//! reward. This wires consensus into the state-transition layer rather than
+//! leaving it purely header-level.
+
+use super::p5_interleave::PowOrPoaDigest;
+use super::p6_pow_retargeting::RetargetingPow;
+use super::p8_merge_pow_poa::MergePowPoa;
+use super::{Consensus, ConsensusAuthority, Header};
+
+/// The slice of state a consensus engine may credit when finalizing a block.
+///
+/// The concrete account `State` implements this so that `finalize` can pay out
+/// a block reward without consensus needing to know the full state layout.
+pub trait BlockReward {
+    /// Credit `amount` to `beneficiary`.
+    fn reward(&mut self, beneficiary: ConsensusAuthority, amount: u64);
+}
+
+/// Block lifecycle hooks layered over a [`Consensus`] engine.
+pub trait Lifecycle: Consensus {
+    /// Populate engine-controlled fields (difficulty target, authority slot,
+    /// timestamp floor) on a partial header before it is sealed. The default is
+    /// a no-op for engines that control no header fields up front.
+    fn prepare(&self, _parent: &Header<Self::Digest>, _partial: &mut Header<()>) {}
+
+    /// Apply post-block effects once a header is sealed, such as paying the
+    /// block reward to the sealer. The default does nothing.
+    fn finalize<S: BlockReward>(&self, _header: &Header<Self::Digest>, _state: &mut S) {}
+}
+
+/// The fixed reward paid to the sealer of each block.
+const BLOCK_REWARD: u64 = 50;
+
+impl Lifecycle for RetargetingPow {
+    fn prepare(&self, parent: &Header<Self::Digest>, partial: &mut Header<()>) {
+        // Enforce a timestamp floor of one tick past the parent so that work is
+        // always mined against a strictly increasing clock.
+        partial.timestamp = partial.timestamp.max(parent.timestamp + 1);
+    }
+
+    fn finalize<S: BlockReward>(&self, _header: &Header<Self::Digest>, state: &mut S) {
+        // Deliberate simplification: `PowDigest` records only a nonce, not a
+        // signer or address, so there is nothing to recover the miner's
+        // identity from. We model a single local miner instead and always pay
+        // the engine's configured `coinbase`, the same role a real miner's
+        // address would play.
+        state.reward(self.coinbase, BLOCK_REWARD);
+    }
+}
+
+impl Lifecycle for MergePowPoa {
+    fn finalize<S: BlockReward>(&self, header: &Header<Self::Digest>, state: &mut S) {
+        // Pre-Merge PoW blocks pay the coinbase-equivalent signer; post-Merge
+        // PoA blocks pay the authority that sealed them.
+        let beneficiary = match header.consensus_digest.seal {
+            PowOrPoaDigest::Pow(_) => self.signer,
+            PowOrPoaDigest::Poa(authority) => authority,
+        };
+        state.reward(beneficiary, BLOCK_REWARD);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use super::super::p6_pow_retargeting::PowDigest;
+    use super::super::p8_merge_pow_poa::MergeDigest;
+
+    #[derive(Default)]
+    struct RewardLedger(Vec<(ConsensusAuthority, u64)>);
+
+    impl BlockReward for RewardLedger {
+        fn reward(&mut self, beneficiary: ConsensusAuthority, amount: u64) {
+            self.0.push((beneficiary, amount));
+        }
+    }
+
+    #[test]
+    fn prepare_raises_a_timestamp_that_does_not_advance_past_the_parent() {
+        let engine = RetargetingPow {
+            window: 10,
+            target_block_time: 10,
+            genesis_threshold: u64::MAX / 2,
+            coinbase: ConsensusAuthority(0),
+        };
+        let parent = Header {
+            parent: 0,
+            height: 0,
+            state_root: 0,
+            extrinsics_root: 0,
+            timestamp: 100,
+            consensus_digest: PowDigest {
+                nonce: 0,
+                threshold: engine.genesis_threshold,
+                window_start: 0,
+                cumulative_work: 0,
+            },
+        };
+        let mut partial = Header {
+            parent: 0,
+            height: 1,
+            state_root: 0,
+            extrinsics_root: 0,
+            timestamp: 100, // Stale clock: same as the parent's.
+            consensus_digest: (),
+        };
+
+        engine.prepare(&parent, &mut partial);
+
+        assert_eq!(partial.timestamp, 101);
+    }
+
+    #[test]
+    fn prepare_leaves_an_already_later_timestamp_alone() {
+        let engine = RetargetingPow {
+            window: 10,
+            target_block_time: 10,
+            genesis_threshold: u64::MAX / 2,
+            coinbase: ConsensusAuthority(0),
+        };
+        let parent = Header {
+            parent: 0,
+            height: 0,
+            state_root: 0,
+            extrinsics_root: 0,
+            timestamp: 100,
+            consensus_digest: PowDigest {
+                nonce: 0,
+                threshold: engine.genesis_threshold,
+                window_start: 0,
+                cumulative_work: 0,
+            },
+        };
+        let mut partial = Header {
+            parent: 0,
+            height: 1,
+            state_root: 0,
+            extrinsics_root: 0,
+            timestamp: 500,
+            consensus_digest: (),
+        };
+
+        engine.prepare(&parent, &mut partial);
+
+        assert_eq!(partial.timestamp, 500);
+    }
+
+    #[test]
+    fn retargeting_pow_finalize_always_pays_the_coinbase() {
+        let engine = RetargetingPow {
+            window: 10,
+            target_block_time: 10,
+            genesis_threshold: u64::MAX / 2,
+            coinbase: ConsensusAuthority(7),
+        };
+        let header = Header {
+            parent: 0,
+            height: 1,
+            state_root: 0,
+            extrinsics_root: 0,
+            timestamp: 0,
+            consensus_digest: PowDigest {
+                nonce: 0,
+                threshold: engine.genesis_threshold,
+                window_start: 0,
+                cumulative_work: 0,
+            },
+        };
+        let mut ledger = RewardLedger::default();
+
+        engine.finalize(&header, &mut ledger);
+
+        assert_eq!(ledger.0, vec![(ConsensusAuthority(7), BLOCK_REWARD)]);
+    }
+
+    #[test]
+    fn merge_pow_poa_finalize_credits_the_sealer_on_each_side_of_the_merge() {
+        let engine = MergePowPoa {
+            terminal_total_difficulty: 3,
+            block_difficulty: 1,
+            threshold: u64::MAX / 2,
+            authorities: vec![ConsensusAuthority(0), ConsensusAuthority(1)],
+            signer: ConsensusAuthority(0),
+        };
+
+        let pow_header = Header {
+            parent: 0,
+            height: 1,
+            state_root: 0,
+            extrinsics_root: 0,
+            timestamp: 0,
+            consensus_digest: MergeDigest {
+                seal: PowOrPoaDigest::Pow(0),
+                total_difficulty: 1,
+            },
+        };
+        let mut ledger = RewardLedger::default();
+        engine.finalize(&pow_header, &mut ledger);
+        assert_eq!(ledger.0, vec![(engine.signer, BLOCK_REWARD)]);
+
+        let poa_header = Header {
+            parent: 0,
+            height: 10,
+            state_root: 0,
+            extrinsics_root: 0,
+            timestamp: 0,
+            consensus_digest: MergeDigest {
+                seal: PowOrPoaDigest::Poa(ConsensusAuthority(1)),
+                total_difficulty: 3,
+            },
+        };
+        let mut ledger = RewardLedger::default();
+        engine.finalize(&poa_header, &mut ledger);
+        assert_eq!(ledger.0, vec![(ConsensusAuthority(1), BLOCK_REWARD)]);
+    }
+}
@@ -0,0 +1,171 @@
+//! The existing PoA assumes a single fixed authority. Production AuRa-style
+//! engines instead verify headers against a validator set stored on-chain and
+//! rotated at epoch boundaries (see Erigon's note that AuRa verifies headers
+//! against validator-set state). `RotatingPoa` divides the chain into
+//! fixed-length epochs and, for each block, loads the authority set active for
+//! that epoch, enforcing round-robin slot assignment.
+
+use super::{Consensus, ConsensusAuthority, Header};
+
+/// A read handle to the validator-set state. The concrete chain state
+/// implements this over the designated validator-set slot, which ordinary
+/// transactions update.
+pub trait ValidatorSet {
+    /// The authorities active for the given epoch.
+    fn authorities_for_epoch(&self, epoch: u64) -> Vec<ConsensusAuthority>;
+}
+
+/// An epoch-rotating PoA engine. The active authority set is read from chain
+/// state at each epoch boundary; within an epoch the sealer rotates round-robin
+/// by block height.
+///
+/// The validator-set handle is carried as a field rather than threaded through
+/// call sites, so that the `Consensus` trait methods themselves — not just the
+/// `_with_state` helpers below — can enforce the authority set. Any caller
+/// that validates or seals through the `Consensus` trait, such as
+/// `ValidateChain`, gets that enforcement without knowing about the
+/// validator set.
+pub struct RotatingPoa<V: ValidatorSet> {
+    /// The number of blocks in an epoch.
+    pub epoch_length: u64,
+    /// The authority this node signs with.
+    pub signer: ConsensusAuthority,
+    /// The read handle onto the on-chain validator set.
+    pub validators: V,
+}
+
+impl<V: ValidatorSet> RotatingPoa<V> {
+    /// The epoch a block at `height` belongs to.
+    fn epoch_of(&self, height: u64) -> u64 {
+        height / self.epoch_length
+    }
+
+    /// The authority scheduled to seal the block at `height`, given the active
+    /// authority set, using round-robin assignment.
+    fn scheduled_author(
+        authorities: &[ConsensusAuthority],
+        height: u64,
+    ) -> Option<ConsensusAuthority> {
+        if authorities.is_empty() {
+            return None;
+        }
+        Some(authorities[(height % authorities.len() as u64) as usize])
+    }
+
+    /// Validate a header against the on-chain validator set: the recovered
+    /// signer must belong to the epoch's authority set and must be the one
+    /// scheduled for this slot.
+    pub fn validate_with_state(&self, header: &Header<ConsensusAuthority>) -> bool {
+        let signer = header.consensus_digest;
+        let authorities = self.validators.authorities_for_epoch(self.epoch_of(header.height));
+        authorities.contains(&signer)
+            && Self::scheduled_author(&authorities, header.height) == Some(signer)
+    }
+
+    /// Seal a block only when this node is the authority scheduled for the
+    /// current slot; otherwise refuse to produce a block.
+    pub fn seal_with_state(&self, partial_header: Header<()>) -> Option<Header<ConsensusAuthority>> {
+        let authorities = self
+            .validators
+            .authorities_for_epoch(self.epoch_of(partial_header.height));
+        if Self::scheduled_author(&authorities, partial_header.height) != Some(self.signer) {
+            return None;
+        }
+        Some(Header {
+            parent: partial_header.parent,
+            height: partial_header.height,
+            state_root: partial_header.state_root,
+            extrinsics_root: partial_header.extrinsics_root,
+            timestamp: partial_header.timestamp,
+            consensus_digest: self.signer,
+        })
+    }
+}
+
+impl<V: ValidatorSet> Consensus for RotatingPoa<V> {
+    type Digest = ConsensusAuthority;
+
+    /// Delegates to [`RotatingPoa::validate_with_state`] so that the authority
+    /// set is enforced for every caller that only knows about `Consensus`.
+    fn validate(&self, _parent_digest: &Self::Digest, header: &Header<Self::Digest>) -> bool {
+        self.validate_with_state(header)
+    }
+
+    /// Delegates to [`RotatingPoa::seal_with_state`], so sealing refuses to
+    /// produce a block when this node is not the scheduled authority.
+    fn seal(
+        &self,
+        _parent_digest: &Self::Digest,
+        partial_header: Header<()>,
+    ) -> Option<Header<Self::Digest>> {
+        self.seal_with_state(partial_header)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    struct FixedAuthorities(Vec<ConsensusAuthority>);
+
+    impl ValidatorSet for FixedAuthorities {
+        fn authorities_for_epoch(&self, _epoch: u64) -> Vec<ConsensusAuthority> {
+            self.0.clone()
+        }
+    }
+
+    fn partial(height: u64) -> Header<()> {
+        Header {
+            parent: 0,
+            height,
+            state_root: 0,
+            extrinsics_root: 0,
+            timestamp: 0,
+            consensus_digest: (),
+        }
+    }
+
+    #[test]
+    fn seal_refuses_when_not_the_scheduled_authority() {
+        // Height 1 is scheduled to authority 1, not authority 0.
+        let engine = RotatingPoa {
+            epoch_length: 10,
+            signer: ConsensusAuthority(0),
+            validators: FixedAuthorities(vec![ConsensusAuthority(0), ConsensusAuthority(1)]),
+        };
+
+        assert!(Consensus::seal(&engine, &ConsensusAuthority(0), partial(1)).is_none());
+    }
+
+    #[test]
+    fn seal_and_validate_agree_for_the_scheduled_authority() {
+        let engine = RotatingPoa {
+            epoch_length: 10,
+            signer: ConsensusAuthority(1),
+            validators: FixedAuthorities(vec![ConsensusAuthority(0), ConsensusAuthority(1)]),
+        };
+
+        let sealed = Consensus::seal(&engine, &ConsensusAuthority(1), partial(1)).unwrap();
+        assert!(Consensus::validate(&engine, &ConsensusAuthority(1), &sealed));
+    }
+
+    #[test]
+    fn validate_rejects_a_signer_outside_the_authority_set() {
+        let engine = RotatingPoa {
+            epoch_length: 10,
+            signer: ConsensusAuthority(0),
+            validators: FixedAuthorities(vec![ConsensusAuthority(0), ConsensusAuthority(1)]),
+        };
+
+        let header = Header {
+            parent: 0,
+            height: 0,
+            state_root: 0,
+            extrinsics_root: 0,
+            timestamp: 0,
+            consensus_digest: ConsensusAuthority(99),
+        };
+
+        assert!(!Consensus::validate(&engine, &ConsensusAuthority(0), &header));
+    }
+}
@@ -13,7 +13,7 @@ use super::{Consensus, ConsensusAuthority, Header};
 /// In order to implement a consensus that can be sealed with either work or a signature,
 /// we will need an enum that wraps the two individual digest types.
 #[derive(Hash, Debug, PartialEq, Eq, Clone, Copy)]
-enum PowOrPoaDigest {
+pub enum PowOrPoaDigest {
     Pow(u64),
     Poa(ConsensusAuthority),
 }
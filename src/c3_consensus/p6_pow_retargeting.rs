@@ -0,0 +1,164 @@
+//! A static work threshold means block production accelerates or stalls as the
+//! network's hashpower changes. Real PoW chains retarget difficulty so that the
+//! average block interval stays roughly constant. Following the
+//! `PowAlgorithm::difficulty(parent)` design from Substrate's `sc_consensus_pow`,
+//! we add a `difficulty` hook to the engine and a concrete `RetargetingPow`
+//! engine that implements Bitcoin-style retargeting.
+
+use super::{Consensus, ConsensusAuthority, Header};
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+
+/// The digest carried by a retargeting PoW block.
+///
+/// As well as the mined `nonce`, the digest records the `threshold` the block
+/// was sealed against and the `window_start` timestamp, so that the next block
+/// can recompute the expected difficulty without walking the whole chain.
+#[derive(Hash, Debug, PartialEq, Eq, Clone, Copy)]
+pub struct PowDigest {
+    /// The nonce that satisfies the work requirement.
+    pub nonce: u64,
+    /// The work threshold this block was mined against. Lower is harder.
+    pub threshold: u64,
+    /// The timestamp at which the current retargeting window began.
+    pub window_start: u64,
+    /// The chain's total work up to and including this block, so fork choice
+    /// can rank tips without walking the whole chain.
+    pub cumulative_work: u64,
+}
+
+/// Convert a work threshold into the work a block sealed against it
+/// contributes to the chain's cumulative total. Lower thresholds are harder to
+/// satisfy and so are worth proportionally more work, mirroring Bitcoin's
+/// `work = 2**256 / (target + 1)`.
+pub fn block_work(threshold: u64) -> u64 {
+    u64::MAX / threshold.max(1)
+}
+
+/// A PoW engine that retargets its work threshold every `window` blocks so that
+/// the average block interval tracks `target_block_time`.
+pub struct RetargetingPow {
+    /// Number of blocks in a retargeting window (`N`).
+    pub window: u64,
+    /// The desired seconds between blocks.
+    pub target_block_time: u64,
+    /// The threshold used for the very first window, before any retarget.
+    pub genesis_threshold: u64,
+    /// The account credited with the block reward when a block is finalized.
+    pub coinbase: ConsensusAuthority,
+}
+
+/// Hash a header down to a single `u64` so we can compare it against a work
+/// threshold. Any seal with `work(header) < threshold` is considered valid.
+fn work<D: Hash>(header: &Header<D>) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    header.hash(&mut hasher);
+    hasher.finish()
+}
+
+impl RetargetingPow {
+    /// The expected number of seconds a full window should take.
+    fn expected_timespan(&self) -> u64 {
+        self.window.saturating_mul(self.target_block_time)
+    }
+
+    /// Compute the work threshold the block at `child_height` must satisfy.
+    ///
+    /// Between window boundaries the threshold is unchanged. On a boundary —
+    /// `child_height` a multiple of `window` — we retarget:
+    /// `new = old * actual_timespan / expected_timespan`, with
+    /// `actual_timespan` clamped to `[expected/4, expected*4]` to damp swings.
+    /// The boundary block is the one that closes the window, so
+    /// `actual_timespan` is measured from `parent_digest.window_start` to
+    /// `child_timestamp` — the timestamp the block itself declares. This is
+    /// the single source of truth for the expected threshold: both `validate`
+    /// and `seal` call it with the child's own height and timestamp so they
+    /// can never disagree.
+    pub fn difficulty(
+        &self,
+        parent_digest: &PowDigest,
+        child_height: u64,
+        child_timestamp: u64,
+    ) -> u64 {
+        if child_height % self.window != 0 {
+            return parent_digest.threshold;
+        }
+
+        let expected = self.expected_timespan();
+        let actual = child_timestamp
+            .saturating_sub(parent_digest.window_start)
+            .clamp(expected / 4, expected.saturating_mul(4));
+
+        let retargeted = (parent_digest.threshold as u128 * actual as u128) / expected as u128;
+        retargeted.min(u64::MAX as u128) as u64
+    }
+
+    /// The `window_start` the sealed block at `child_height` should carry: the
+    /// parent's `window_start` unchanged, unless this block opens a new
+    /// window, in which case the window starts now.
+    fn window_start_for(
+        &self,
+        parent_digest: &PowDigest,
+        child_height: u64,
+        child_timestamp: u64,
+    ) -> u64 {
+        if child_height % self.window == 0 {
+            child_timestamp
+        } else {
+            parent_digest.window_start
+        }
+    }
+}
+
+impl Consensus for RetargetingPow {
+    type Digest = PowDigest;
+
+    fn validate(&self, parent_digest: &Self::Digest, header: &Header<Self::Digest>) -> bool {
+        let expected = self.difficulty(parent_digest, header.height, header.timestamp);
+        let expected_cumulative_work = parent_digest
+            .cumulative_work
+            .saturating_add(block_work(expected));
+        // The block must declare the threshold we expect, carry forward the
+        // matching cumulative work total, and its work must meet that
+        // threshold. The timestamp must not precede the start of the current
+        // window; the digest does not carry the parent's own timestamp.
+        header.consensus_digest.threshold == expected
+            && header.consensus_digest.cumulative_work == expected_cumulative_work
+            && header.timestamp >= parent_digest.window_start
+            && work(header) < expected
+    }
+
+    fn seal(
+        &self,
+        parent_digest: &Self::Digest,
+        partial_header: Header<()>,
+    ) -> Option<Header<Self::Digest>> {
+        let expected =
+            self.difficulty(parent_digest, partial_header.height, partial_header.timestamp);
+        let window_start =
+            self.window_start_for(parent_digest, partial_header.height, partial_header.timestamp);
+        let cumulative_work = parent_digest
+            .cumulative_work
+            .saturating_add(block_work(expected));
+
+        for nonce in 0..u64::MAX {
+            let candidate = Header {
+                parent: partial_header.parent,
+                height: partial_header.height,
+                state_root: partial_header.state_root,
+                extrinsics_root: partial_header.extrinsics_root,
+                timestamp: partial_header.timestamp,
+                consensus_digest: PowDigest {
+                    nonce,
+                    threshold: expected,
+                    window_start,
+                    cumulative_work,
+                },
+            };
+            if work(&candidate) < expected {
+                return Some(candidate);
+            }
+        }
+        None
+    }
+}
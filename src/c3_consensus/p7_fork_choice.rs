@@ -0,0 +1,134 @@
+//! `Consensus` only exposes `validate`/`seal`, so on its own the crate cannot
+//! decide between two equally valid branches. Borrowing the fork-choice
+//! abstraction from Erigon's consensus-engine separation and
+//! `PowAlgorithm::break_tie`, we add a `compare_tips` method that establishes
+//! the partial order used for canonicalization, plus a `best_tip` convenience
+//! that folds it across candidates. This gives chain-import code a single place
+//! to resolve reorgs.
+
+use super::{Consensus, Header};
+use std::cmp::Ordering;
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+
+use super::p11_rotating_poa::{RotatingPoa, ValidatorSet};
+use super::p6_pow_retargeting::RetargetingPow;
+
+/// Collapse a header to a single value for deterministic tie-breaking. The
+/// branch with the lower seal/hash is treated as "earliest seen", which
+/// discourages selfish mining.
+fn seal_value<D: Hash>(header: &Header<D>) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    header.hash(&mut hasher);
+    hasher.finish()
+}
+
+/// Fork-choice rule layered on top of a [`Consensus`] engine.
+///
+/// The default ranks by height and breaks ties toward the lower seal value.
+/// Work-based engines should override `compare_tips` to rank by cumulative
+/// work instead.
+pub trait ForkChoice: Consensus {
+    /// Order two tips. `Ordering::Greater` means `a` is the better (more
+    /// canonical) tip.
+    fn compare_tips(&self, a: &Header<Self::Digest>, b: &Header<Self::Digest>) -> Ordering {
+        a.height
+            .cmp(&b.height)
+            .then_with(|| seal_value(b).cmp(&seal_value(a)))
+    }
+
+    /// Fold [`ForkChoice::compare_tips`] across a slice of candidate tips,
+    /// returning the best one (or `None` if the slice is empty).
+    fn best_tip<'a>(
+        &self,
+        tips: &'a [Header<Self::Digest>],
+    ) -> Option<&'a Header<Self::Digest>> {
+        tips.iter().reduce(|best, candidate| {
+            match self.compare_tips(candidate, best) {
+                Ordering::Greater => candidate,
+                _ => best,
+            }
+        })
+    }
+}
+
+impl ForkChoice for RetargetingPow {
+    /// Rank by cumulative work rather than height. Each `PowDigest` carries the
+    /// chain's running total as of that block (summed, not approximated, as
+    /// every block is sealed — see `p6_pow_retargeting::block_work`), so this
+    /// correctly ranks forks even across a difficulty retarget. On an exact tie
+    /// we fall back to the lower seal value so that the earliest-seen block
+    /// wins deterministically.
+    fn compare_tips(&self, a: &Header<Self::Digest>, b: &Header<Self::Digest>) -> Ordering {
+        a.consensus_digest
+            .cumulative_work
+            .cmp(&b.consensus_digest.cumulative_work)
+            .then_with(|| seal_value(b).cmp(&seal_value(a)))
+    }
+}
+
+impl<V: ValidatorSet> ForkChoice for RotatingPoa<V> {
+    /// PoA has no work to accumulate, so the default's height ranking is
+    /// already correct. Ties are broken by the signer's position in the
+    /// epoch's round-robin authority list — earlier in the schedule wins —
+    /// rather than the seal-value fallback, which carries no meaning for a
+    /// signature digest.
+    fn compare_tips(&self, a: &Header<Self::Digest>, b: &Header<Self::Digest>) -> Ordering {
+        a.height.cmp(&b.height).then_with(|| {
+            let authorities = self
+                .validators
+                .authorities_for_epoch(a.height / self.epoch_length);
+            let rank = |signer| {
+                authorities
+                    .iter()
+                    .position(|&authority| authority == signer)
+                    .unwrap_or(usize::MAX)
+            };
+            // Lower rank (earlier in the schedule) is better, so compare in
+            // reverse: `Ordering::Greater` must mean `a` is the better tip.
+            rank(b.consensus_digest).cmp(&rank(a.consensus_digest))
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::c3_consensus::ConsensusAuthority;
+    use crate::c3_consensus::p6_pow_retargeting::PowDigest;
+
+    fn pow_tip(height: u64, threshold: u64, cumulative_work: u64) -> Header<PowDigest> {
+        Header {
+            parent: 0,
+            height,
+            state_root: 0,
+            extrinsics_root: 0,
+            timestamp: 0,
+            consensus_digest: PowDigest {
+                nonce: 0,
+                threshold,
+                window_start: 0,
+                cumulative_work,
+            },
+        }
+    }
+
+    #[test]
+    fn pow_compare_tips_prefers_more_cumulative_work_over_height() {
+        let engine = RetargetingPow {
+            window: 10,
+            target_block_time: 10,
+            genesis_threshold: u64::MAX / 2,
+            coinbase: ConsensusAuthority(0),
+        };
+
+        // Fewer, harder blocks can outweigh a taller but easier fork.
+        let shallow_but_heavy = pow_tip(3, 1, 1_000_000);
+        let tall_but_light = pow_tip(10, u64::MAX, 10);
+
+        assert_eq!(
+            engine.compare_tips(&shallow_but_heavy, &tall_but_light),
+            Ordering::Greater
+        );
+    }
+}
@@ -0,0 +1,233 @@
+//! `AlternatingPowPoa` flips consensus every block, but the historically
+//! interesting transition — early Ethereum's actual Merge — is a permanent
+//! cutover once accumulated PoW crosses a threshold. `MergePowPoa` models that:
+//! blocks are PoW-sealed until the chain's cumulative difficulty reaches a
+//! configured terminal total difficulty (TTD), after which every descendant is
+//! PoA-sealed. Per EIP-3675, no PoW block may descend from a terminal PoW block.
+
+use super::p5_interleave::PowOrPoaDigest;
+use super::{Consensus, ConsensusAuthority, Header};
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+
+/// A merge digest wraps a PoW/PoA seal together with the chain's cumulative
+/// difficulty as of this block, so that `validate`/`seal` can decide which side
+/// of the Merge a block sits on without re-walking the chain.
+#[derive(Hash, Debug, PartialEq, Eq, Clone, Copy)]
+pub struct MergeDigest {
+    /// The actual seal: work nonce before the Merge, authority after it.
+    pub seal: PowOrPoaDigest,
+    /// Cumulative difficulty up to and including this block.
+    pub total_difficulty: u64,
+}
+
+/// A one-way PoW → PoA merge engine driven by a terminal total difficulty.
+pub struct MergePowPoa {
+    /// The cumulative difficulty at which the chain cuts over to PoA.
+    pub terminal_total_difficulty: u64,
+    /// The difficulty every PoW block contributes.
+    pub block_difficulty: u64,
+    /// The work threshold a PoW seal must beat.
+    pub threshold: u64,
+    /// The authority set trusted to seal post-Merge blocks.
+    pub authorities: Vec<ConsensusAuthority>,
+    /// The authority this node signs with when producing a PoA block.
+    pub signer: ConsensusAuthority,
+}
+
+fn work(header: &Header<MergeDigest>) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    header.hash(&mut hasher);
+    hasher.finish()
+}
+
+impl MergePowPoa {
+    /// Whether a parent at `total_difficulty` has reached the terminal block,
+    /// meaning its children must be PoA-sealed.
+    fn parent_crossed(&self, total_difficulty: u64) -> bool {
+        total_difficulty >= self.terminal_total_difficulty
+    }
+}
+
+impl Consensus for MergePowPoa {
+    type Digest = MergeDigest;
+
+    fn validate(&self, parent_digest: &Self::Digest, header: &Header<Self::Digest>) -> bool {
+        let crossed = self.parent_crossed(parent_digest.total_difficulty);
+        match header.consensus_digest.seal {
+            PowOrPoaDigest::Pow(_) => {
+                // EIP-3675: a PoW block may never descend from a terminal block.
+                if crossed {
+                    return false;
+                }
+                let expected = parent_digest.total_difficulty + self.block_difficulty;
+                header.consensus_digest.total_difficulty == expected && work(header) < self.threshold
+            }
+            PowOrPoaDigest::Poa(authority) => {
+                // PoA is only legal once the parent has crossed the TTD, and the
+                // signer must be a known authority. PoA adds no work.
+                crossed
+                    && self.authorities.contains(&authority)
+                    && header.consensus_digest.total_difficulty == parent_digest.total_difficulty
+            }
+        }
+    }
+
+    fn seal(
+        &self,
+        parent_digest: &Self::Digest,
+        partial_header: Header<()>,
+    ) -> Option<Header<Self::Digest>> {
+        if self.parent_crossed(parent_digest.total_difficulty) {
+            // Post-Merge: seal with our authority, carrying difficulty forward.
+            Some(Header {
+                parent: partial_header.parent,
+                height: partial_header.height,
+                state_root: partial_header.state_root,
+                extrinsics_root: partial_header.extrinsics_root,
+                timestamp: partial_header.timestamp,
+                consensus_digest: MergeDigest {
+                    seal: PowOrPoaDigest::Poa(self.signer),
+                    total_difficulty: parent_digest.total_difficulty,
+                },
+            })
+        } else {
+            // Pre-Merge: mine work and accumulate difficulty.
+            let total_difficulty = parent_digest.total_difficulty + self.block_difficulty;
+            for nonce in 0..u64::MAX {
+                let candidate = Header {
+                    parent: partial_header.parent,
+                    height: partial_header.height,
+                    state_root: partial_header.state_root,
+                    extrinsics_root: partial_header.extrinsics_root,
+                    timestamp: partial_header.timestamp,
+                    consensus_digest: MergeDigest {
+                        seal: PowOrPoaDigest::Pow(nonce),
+                        total_difficulty,
+                    },
+                };
+                if work(&candidate) < self.threshold {
+                    return Some(candidate);
+                }
+            }
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn engine() -> MergePowPoa {
+        MergePowPoa {
+            terminal_total_difficulty: 3,
+            block_difficulty: 1,
+            threshold: u64::MAX / 2,
+            authorities: vec![ConsensusAuthority(0), ConsensusAuthority(1)],
+            signer: ConsensusAuthority(0),
+        }
+    }
+
+    fn partial(height: u64) -> Header<()> {
+        Header {
+            parent: 0,
+            height,
+            state_root: 0,
+            extrinsics_root: 0,
+            timestamp: 0,
+            consensus_digest: (),
+        }
+    }
+
+    #[test]
+    fn seal_mines_pow_before_the_terminal_difficulty() {
+        let engine = engine();
+        let genesis = MergeDigest {
+            seal: PowOrPoaDigest::Pow(0),
+            total_difficulty: 0,
+        };
+
+        let sealed = engine.seal(&genesis, partial(1)).unwrap();
+
+        assert!(matches!(sealed.consensus_digest.seal, PowOrPoaDigest::Pow(_)));
+        assert_eq!(sealed.consensus_digest.total_difficulty, 1);
+        assert!(engine.validate(&genesis, &sealed));
+    }
+
+    #[test]
+    fn seal_switches_to_poa_once_the_parent_has_crossed_the_ttd() {
+        let engine = engine();
+        let crossed_parent = MergeDigest {
+            seal: PowOrPoaDigest::Pow(0),
+            total_difficulty: engine.terminal_total_difficulty,
+        };
+
+        let sealed = engine.seal(&crossed_parent, partial(10)).unwrap();
+
+        assert_eq!(
+            sealed.consensus_digest.seal,
+            PowOrPoaDigest::Poa(engine.signer)
+        );
+        // PoA blocks carry no additional difficulty.
+        assert_eq!(
+            sealed.consensus_digest.total_difficulty,
+            engine.terminal_total_difficulty
+        );
+        assert!(engine.validate(&crossed_parent, &sealed));
+    }
+
+    #[test]
+    fn validate_rejects_a_pow_block_descending_from_a_terminal_block() {
+        // EIP-3675: once the parent has crossed the TTD, no PoW block may
+        // follow it, even one that would otherwise satisfy the work check.
+        let engine = engine();
+        let crossed_parent = MergeDigest {
+            seal: PowOrPoaDigest::Pow(0),
+            total_difficulty: engine.terminal_total_difficulty,
+        };
+        // Find a nonce that actually satisfies the work check, so the test
+        // exercises the Merge rule rather than a trivially-failing seal.
+        let mut header = Header {
+            parent: 0,
+            height: 10,
+            state_root: 0,
+            extrinsics_root: 0,
+            timestamp: 0,
+            consensus_digest: MergeDigest {
+                seal: PowOrPoaDigest::Pow(0),
+                total_difficulty: engine.terminal_total_difficulty + engine.block_difficulty,
+            },
+        };
+        for nonce in 0..10_000u64 {
+            header.consensus_digest.seal = PowOrPoaDigest::Pow(nonce);
+            if work(&header) < engine.threshold {
+                break;
+            }
+        }
+
+        assert!(!engine.validate(&crossed_parent, &header));
+    }
+
+    #[test]
+    fn validate_rejects_poa_from_an_unknown_authority() {
+        let engine = engine();
+        let crossed_parent = MergeDigest {
+            seal: PowOrPoaDigest::Pow(0),
+            total_difficulty: engine.terminal_total_difficulty,
+        };
+        let header = Header {
+            parent: 0,
+            height: 10,
+            state_root: 0,
+            extrinsics_root: 0,
+            timestamp: 0,
+            consensus_digest: MergeDigest {
+                seal: PowOrPoaDigest::Poa(ConsensusAuthority(99)),
+                total_difficulty: engine.terminal_total_difficulty,
+            },
+        };
+
+        assert!(!engine.validate(&crossed_parent, &header));
+    }
+}
@@ -0,0 +1,190 @@
+//! A bare `bool` from `validate` collapses every failure mode into one, so
+//! callers cannot tell a transient rejection from a permanent one. Following
+//! reth's `Consensus` split into `validate_header`, `validate_header_against_parent`
+//! and `validate_header_range`, we layer a typed, parent-relative validation
+//! interface over any [`Consensus`] engine and return a [`ConsensusError`]
+//! describing which rule was broken. Seal failures are reported as `BadSeal`
+//! unless the engine classifies them more finely.
+
+use super::p6_pow_retargeting::{PowDigest, RetargetingPow};
+use super::p8_merge_pow_poa::MergePowPoa;
+use super::p11_rotating_poa::{RotatingPoa, ValidatorSet};
+use super::{Consensus, Header};
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+
+/// The distinct ways a header can be rejected, so callers can distinguish
+/// transient from permanent failures.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ConsensusError {
+    /// The seal (work or signature) did not validate.
+    BadSeal,
+    /// The header's height was not exactly one more than its parent's.
+    NonMonotonicHeight,
+    /// The header's timestamp predates its parent's.
+    TimestampInPast,
+    /// The header did not link back to the given parent.
+    WrongParent,
+    /// The header declared a difficulty that disagrees with the engine.
+    WrongDifficulty,
+}
+
+fn hash_of<D: Hash>(header: &Header<D>) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    header.hash(&mut hasher);
+    hasher.finish()
+}
+
+/// Typed, parent-relative validation layered over a [`Consensus`] engine.
+pub trait ValidateChain: Consensus {
+    /// Classify why [`Consensus::validate`] rejected a header, beyond the bare
+    /// `false` it returns. The default collapses every failure to `BadSeal`;
+    /// engines with a finer-grained, distinguishable failure mode (e.g. a
+    /// declared difficulty that disagrees with the engine's own expectation)
+    /// should override this to report it precisely.
+    fn classify_seal_failure(
+        &self,
+        _parent_digest: &Self::Digest,
+        _header: &Header<Self::Digest>,
+    ) -> ConsensusError {
+        ConsensusError::BadSeal
+    }
+
+    /// A standalone sanity check of a single header's seal against the parent
+    /// digest it claims to build on.
+    fn validate_header(
+        &self,
+        parent_digest: &Self::Digest,
+        header: &Header<Self::Digest>,
+    ) -> Result<(), ConsensusError> {
+        if self.validate(parent_digest, header) {
+            Ok(())
+        } else {
+            Err(self.classify_seal_failure(parent_digest, header))
+        }
+    }
+
+    /// Enforce the relationships a header must hold with its parent: monotonic
+    /// block numbers, non-decreasing timestamps, correct parent linkage, and a
+    /// valid seal.
+    fn validate_against_parent(
+        &self,
+        parent: &Header<Self::Digest>,
+        header: &Header<Self::Digest>,
+    ) -> Result<(), ConsensusError> {
+        if header.height != parent.height + 1 {
+            return Err(ConsensusError::NonMonotonicHeight);
+        }
+        if header.timestamp < parent.timestamp {
+            return Err(ConsensusError::TimestampInPast);
+        }
+        if header.parent != hash_of(parent) {
+            return Err(ConsensusError::WrongParent);
+        }
+        self.validate_header(&parent.consensus_digest, header)
+    }
+
+    /// Walk a slice of consecutive headers applying both checks, reporting the
+    /// index of the first offender alongside the reason.
+    fn validate_range(
+        &self,
+        headers: &[Header<Self::Digest>],
+    ) -> Result<(), (usize, ConsensusError)> {
+        for (index, pair) in headers.windows(2).enumerate() {
+            let [parent, child] = [&pair[0], &pair[1]];
+            if let Err(err) = self.validate_against_parent(parent, child) {
+                // `index` is the parent; the offending child is `index + 1`.
+                return Err((index + 1, err));
+            }
+        }
+        Ok(())
+    }
+}
+
+impl ValidateChain for RetargetingPow {
+    /// A wrong threshold is a distinguishable, deterministic failure (the
+    /// engine can recompute the one correct value and compare); anything else
+    /// that still fails `validate` is a bad seal.
+    fn classify_seal_failure(
+        &self,
+        parent_digest: &Self::Digest,
+        header: &Header<Self::Digest>,
+    ) -> ConsensusError {
+        let expected = self.difficulty(parent_digest, header.height, header.timestamp);
+        if header.consensus_digest.threshold != expected {
+            ConsensusError::WrongDifficulty
+        } else {
+            ConsensusError::BadSeal
+        }
+    }
+}
+
+impl ValidateChain for MergePowPoa {}
+
+impl<V: ValidatorSet> ValidateChain for RotatingPoa<V> {}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::c3_consensus::ConsensusAuthority;
+
+    fn engine() -> RetargetingPow {
+        RetargetingPow {
+            window: 4,
+            target_block_time: 10,
+            genesis_threshold: u64::MAX / 2,
+            coinbase: ConsensusAuthority(0),
+        }
+    }
+
+    #[test]
+    fn validate_header_reports_wrong_difficulty_not_bad_seal() {
+        let engine = engine();
+        let parent_digest = PowDigest {
+            nonce: 0,
+            threshold: engine.genesis_threshold,
+            window_start: 0,
+            cumulative_work: 0,
+        };
+        let partial = Header {
+            parent: 0,
+            height: 1,
+            state_root: 0,
+            extrinsics_root: 0,
+            timestamp: 5,
+            consensus_digest: (),
+        };
+        let mut sealed = engine.seal(&parent_digest, partial).unwrap();
+        // Corrupt the declared threshold so the header lies about the
+        // difficulty it was mined at. This also changes the header's hash, so
+        // the work may no longer pass either; the threshold is checked first.
+        sealed.consensus_digest.threshold = sealed.consensus_digest.threshold.wrapping_add(1);
+
+        assert_eq!(
+            engine.validate_header(&parent_digest, &sealed),
+            Err(ConsensusError::WrongDifficulty)
+        );
+    }
+
+    #[test]
+    fn validate_header_accepts_a_correctly_sealed_block() {
+        let engine = engine();
+        let parent_digest = PowDigest {
+            nonce: 0,
+            threshold: engine.genesis_threshold,
+            window_start: 0,
+            cumulative_work: 0,
+        };
+        let partial = Header {
+            parent: 0,
+            height: 1,
+            state_root: 0,
+            extrinsics_root: 0,
+            timestamp: 5,
+            consensus_digest: (),
+        };
+        let sealed = engine.seal(&parent_digest, partial).unwrap();
+
+        assert_eq!(engine.validate_header(&parent_digest, &sealed), Ok(()));
+    }
+}
